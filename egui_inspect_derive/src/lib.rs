@@ -1,12 +1,14 @@
+use std::collections::HashSet;
+
 use proc_macro2::{Ident, TokenStream};
 use quote::{quote, quote_spanned, format_ident, ToTokens};
 use syn::spanned::Spanned;
 use syn::{
-    parse_macro_input, parse_quote, Data, DataEnum, DeriveInput, Field, Fields, FieldsNamed,
-    GenericParam, Generics, Index, Variant, FieldsUnnamed,
+    parse_macro_input, parse_quote, Data, DataEnum, DeriveInput, Expr, ExprLit, ExprUnary, Field,
+    Fields, FieldsNamed, GenericParam, Generics, Index, Lit, Type, UnOp, Variant, FieldsUnnamed,
 };
 
-use darling::{FromField, FromMeta, FromVariant};
+use darling::{FromDeriveInput, FromField, FromMeta, FromVariant};
 
 mod internal_paths;
 mod utils;
@@ -34,6 +36,46 @@ struct AttributeArgs {
     custom_func_mut: Option<String>,
 }
 
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(inspect), default)]
+struct ContainerAttributeArgs {
+    /// When switching enum variants in the combo, carry over field values that have
+    /// the same type instead of always resetting every field to `Default::default()`
+    carry_fields: bool,
+    /// For a fieldless enum with explicit discriminants, expose it as an editable
+    /// integer instead of a name label/combo
+    as_int: bool,
+    /// Use slider function for `as_int`
+    slider: bool,
+    /// Min value for `as_int`; defaults to the lowest discriminant in the enum
+    min: Option<f32>,
+    /// Max value for `as_int`; defaults to the highest discriminant in the enum
+    max: Option<f32>,
+}
+
+impl Default for ContainerAttributeArgs {
+    fn default() -> Self {
+        Self {
+            carry_fields: false,
+            as_int: false,
+            slider: true,
+            min: None,
+            max: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, FromVariant)]
+#[darling(attributes(inspect), default)]
+struct VariantAttributeArgs {
+    /// Path to a `fn() -> Self` used to build this variant when it's selected in the combo,
+    /// instead of requiring every field to implement `Default`
+    construct_with: Option<String>,
+    /// Path to a `fn(&Self) -> bool` used to decide whether `self` is currently this variant,
+    /// instead of requiring the whole enum to implement `PartialEq`
+    variant_eq: Option<String>,
+}
+
 impl Default for AttributeArgs {
     fn default() -> Self {
         Self {
@@ -54,14 +96,17 @@ impl Default for AttributeArgs {
 pub fn derive_egui_inspect(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
+    let container_attrs = ContainerAttributeArgs::from_derive_input(&input)
+        .expect("Could not get attributes from container");
+
     let name = input.ident;
 
-    let generics = add_trait_bounds(input.generics);
+    let generics = add_trait_bounds(input.generics, &input.data);
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let inspect = inspect_data(&input.data, &name, false);
+    let inspect = inspect_data(&input.data, &name, false, &container_attrs);
 
-    let inspect_mut = inspect_data(&input.data, &name, true);
+    let inspect_mut = inspect_data(&input.data, &name, true, &container_attrs);
 
     let expanded = quote! {
         impl #impl_generics egui_inspect::EguiInspect for #name #ty_generics #where_clause {
@@ -77,18 +122,79 @@ pub fn derive_egui_inspect(input: proc_macro::TokenStream) -> proc_macro::TokenS
     proc_macro::TokenStream::from(expanded)
 }
 
-fn add_trait_bounds(mut generics: Generics) -> Generics {
-    for param in &mut generics.params {
-        if let GenericParam::Type(ref mut type_param) = *param {
-            type_param
-                .bounds
-                .push(parse_quote!(egui_inspect::EguiInspect));
+/// Adds a `where Field: EguiInspect` predicate for every field type that mentions a
+/// type parameter and actually goes through the default inspect path, instead of blindly bounding every type
+/// parameter. This keeps generics that are only used in `PhantomData<T>`, hidden
+/// fields, or fields rendered through `custom_func`/`custom_func_mut` from forcing
+/// an `EguiInspect` bound they don't need.
+fn add_trait_bounds(mut generics: Generics, data: &Data) -> Generics {
+    let generic_idents: HashSet<Ident> = generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(type_param) => Some(type_param.ident.clone()),
+            _ => None,
+        })
+        .collect();
+
+    if generic_idents.is_empty() {
+        return generics;
+    }
+
+    let where_clause = generics.make_where_clause();
+    let mut seen = HashSet::new();
+    for field in all_fields(data) {
+        let attrs = AttributeArgs::from_field(field).expect("Could not get attributes from field");
+        // Tuple fields are always inspected through the default path (their attributes are not read).
+        if field.ident.is_some()
+            && !field_uses_default_path(&attrs, true)
+            && !field_uses_default_path(&attrs, false)
+        {
+            continue;
+        }
+        if !utils::type_contains_generic(&field.ty, &generic_idents) {
+            continue;
+        }
+        let key = field.ty.to_token_stream().to_string();
+        if seen.insert(key) {
+            let ty = &field.ty;
+            where_clause
+                .predicates
+                .push(parse_quote!(#ty: egui_inspect::EguiInspect));
         }
     }
     generics
 }
 
-fn inspect_data(data: &Data, name: &Ident, mutable: bool) -> TokenStream {
+/// All fields across a struct's single `Fields` or an enum's variants, in declaration order.
+fn all_fields(data: &Data) -> Vec<&Field> {
+    match data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data_enum) => data_enum
+            .variants
+            .iter()
+            .flat_map(|variant| variant.fields.iter())
+            .collect(),
+        Data::Union(_) => unimplemented!("Unions are not yet supported"),
+    }
+}
+
+/// Whether, for the given direction, this field is rendered through the default
+/// `EguiInspect::inspect`/`inspect_mut` call rather than being hidden or routed
+/// through a `custom_func`/`custom_func_mut`. Mirrors the logic in `handle_custom_func`.
+fn field_uses_default_path(attrs: &AttributeArgs, direction_mutable: bool) -> bool {
+    if attrs.hide {
+        return false;
+    }
+    let effective_mutable = direction_mutable && !attrs.no_edit;
+    if effective_mutable {
+        attrs.custom_func_mut.is_none()
+    } else {
+        attrs.custom_func.is_none()
+    }
+}
+
+fn inspect_data(data: &Data, name: &Ident, mutable: bool, container_attrs: &ContainerAttributeArgs) -> TokenStream {
     match *data {
         Data::Struct(ref data) => {
             let fields = inspect_fields(&data.fields, true, mutable);
@@ -97,20 +203,49 @@ fn inspect_data(data: &Data, name: &Ident, mutable: bool) -> TokenStream {
                 #(#fields)*
             }
         },
-        Data::Enum(ref data_enum) => inspect_enum(data_enum, name, mutable),
+        Data::Enum(ref data_enum) => inspect_enum(data_enum, name, mutable, container_attrs),
         Data::Union(_) => unimplemented!("Unions are not yet supported"),
     }
 }
 
-fn inspect_enum(data_enum: &DataEnum, name: &Ident, mutable: bool) -> TokenStream {
+fn inspect_enum(data_enum: &DataEnum, name: &Ident, mutable: bool, container_attrs: &ContainerAttributeArgs) -> TokenStream {
     let variants: Vec<_> = data_enum.variants.iter().collect();
-    let name_arms = variants.iter().map(|v| variant_name_arm(v, name));
+    let is_fieldless = variants.iter().all(|v| matches!(v.fields, Fields::Unit));
+    let discriminants = if is_fieldless {
+        Some(compute_discriminants(&variants))
+    } else {
+        None
+    };
+
+    if container_attrs.as_int && is_fieldless {
+        let discriminants = discriminants.as_ref().unwrap();
+        let resolved: Vec<i64> = variants
+            .iter()
+            .zip(discriminants)
+            .map(|(v, d)| {
+                d.unwrap_or_else(|| {
+                    panic!(
+                        "#[inspect(as_int)] requires every variant to have a constant-foldable discriminant, but `{}` does not",
+                        v.ident
+                    )
+                })
+            })
+            .collect();
+        return inspect_enum_as_int(&variants, &resolved, name, mutable, container_attrs);
+    }
+
+    let name_arms = variants.iter().enumerate().map(|(i, v)| {
+        let discriminant = discriminants.as_ref().and_then(|ds| ds[i]);
+        variant_name_arm(v, name, discriminant)
+    });
     let reflect_variant_name = quote!(
         let current_variant = match self {
             #(#name_arms,)*
         };
     );
-    let combo_opts = variants.iter().map(|v| variant_combo(v, name));
+    let combo_opts = variants
+        .iter()
+        .map(|v| variant_combo(v, name, &variants, container_attrs));
     let combo = if mutable {
         quote!{
             ui.horizontal(|ui| {
@@ -135,23 +270,145 @@ fn inspect_enum(data_enum: &DataEnum, name: &Ident, mutable: bool) -> TokenStrea
     )
 }
 
-fn variant_name_arm(variant: &Variant, struct_name: &Ident) -> TokenStream {
+fn variant_name_arm(variant: &Variant, struct_name: &Ident, discriminant: Option<i64>) -> TokenStream {
     let ident = &variant.ident;
+    let name_expr = match discriminant {
+        Some(value) => quote!(format!("{} ({})", stringify!(#ident), #value)),
+        None => quote!(stringify!(#ident).to_string()),
+    };
     match &variant.fields {
         Fields::Named(_) => {
-            quote!(#struct_name::#ident {..} => stringify!(#ident))
+            quote!(#struct_name::#ident {..} => #name_expr)
         }
         Fields::Unnamed(_) => {
-            quote!(#struct_name::#ident (..) => stringify!(#ident))
+            quote!(#struct_name::#ident (..) => #name_expr)
         }
         Fields::Unit => {
-            quote!(#struct_name::#ident => stringify!(#ident))
+            quote!(#struct_name::#ident => #name_expr)
         }
     }
 }
 
-fn variant_combo(variant: &Variant, struct_name: &Ident) -> TokenStream {
+/// The integer discriminant of each variant, in declaration order: the explicit
+/// value when given, otherwise one more than the previous variant's (starting at 0),
+/// matching the rules the compiler itself uses for C-like enums. A discriminant
+/// expression that isn't an integer literal (e.g. a named const) can't be const-folded
+/// here, so it yields `None` — as does every later variant relying on it implicitly,
+/// since its numeric value is likewise unknown.
+fn compute_discriminants(variants: &[&Variant]) -> Vec<Option<i64>> {
+    let mut next = Some(0i64);
+    variants
+        .iter()
+        .map(|variant| {
+            let value = match &variant.discriminant {
+                Some((_, expr)) => parse_discriminant_expr(expr),
+                None => next,
+            };
+            next = value.map(|value| value + 1);
+            value
+        })
+        .collect()
+}
+
+fn parse_discriminant_expr(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit_int),
+            ..
+        }) => lit_int.base10_parse::<i64>().ok(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => parse_discriminant_expr(expr).map(|value| -value),
+        _ => None,
+    }
+}
+
+/// `#[inspect(as_int)]` codegen for fieldless enums: shows/edits the discriminant as
+/// a plain integer instead of a name label/combo, mapping edits back onto the
+/// matching variant and leaving `self` unchanged if the value matches none.
+fn inspect_enum_as_int(
+    variants: &[&Variant],
+    discriminants: &[i64],
+    struct_name: &Ident,
+    mutable: bool,
+    container_attrs: &ContainerAttributeArgs,
+) -> TokenStream {
+    let value_arms = variants.iter().zip(discriminants).map(|(v, value)| {
+        let ident = &v.ident;
+        quote!(#struct_name::#ident => #value)
+    });
+
+    if !mutable {
+        return quote!(
+            let __value: i64 = match self { #(#value_arms,)* };
+            ui.strong(label);
+            ui.label(format!("{}", __value));
+        );
+    }
+
+    let assign_arms = variants.iter().zip(discriminants).map(|(v, value)| {
+        let ident = &v.ident;
+        quote!(#value => *self = #struct_name::#ident)
+    });
+    let min = container_attrs.min.map(|min| min as i64).unwrap_or_else(|| {
+        *discriminants.iter().min().expect("enum has at least one variant")
+    });
+    let max = container_attrs.max.map(|max| max as i64).unwrap_or_else(|| {
+        *discriminants.iter().max().expect("enum has at least one variant")
+    });
+    let widget = if container_attrs.slider {
+        quote!(ui.add(egui::Slider::new(&mut __value, #min..=#max)))
+    } else {
+        quote!(ui.add(egui::DragValue::new(&mut __value).clamp_range(#min..=#max)))
+    };
+    quote!(
+        let mut __value: i64 = match self { #(#value_arms,)* };
+        ui.horizontal(|ui| {
+            ui.strong(label);
+            if #widget.changed() {
+                match __value {
+                    #(#assign_arms,)*
+                    _ => {}
+                }
+            }
+        });
+    )
+}
+
+fn variant_combo(
+    variant: &Variant,
+    struct_name: &Ident,
+    all_variants: &[&Variant],
+    container_attrs: &ContainerAttributeArgs,
+) -> TokenStream {
     let ident = &variant.ident;
+    let attrs = VariantAttributeArgs::from_variant(variant)
+        .expect("Could not get attributes from variant");
+
+    if let Some(construct_with) = &attrs.construct_with {
+        let construct_fn = syn::Path::from_string(construct_with)
+            .expect(format!("Could not find function: {}", construct_with).as_str());
+        let is_selected = variant_is_selected(variant, struct_name, &attrs);
+        return quote!(
+            if ui.selectable_label(#is_selected, stringify!(#ident)).clicked() {
+                *self = #construct_fn();
+            }
+        );
+    }
+
+    if container_attrs.carry_fields {
+        return variant_combo_carry_fields(variant, struct_name, all_variants, &attrs);
+    }
+
+    if attrs.variant_eq.is_some() {
+        panic!(
+            "#[inspect(variant_eq = \"...\")] on variant `{}` has no effect without #[inspect(construct_with)] or the container-level #[inspect(carry_fields)] \u{2014} remove it or add one of those attributes",
+            ident
+        );
+    }
+
     match &variant.fields {
         Fields::Named(fields) => {
             let defaults = fields
@@ -161,8 +418,8 @@ fn variant_combo(variant: &Variant, struct_name: &Ident) -> TokenStream {
                     let ident = f.ident.clone();
                     quote!( #ident: Default::default() )}
                 );
-            quote!(ui.selectable_value(self, 
-                                       #struct_name::#ident { #(#defaults),* }, 
+            quote!(ui.selectable_value(self,
+                                       #struct_name::#ident { #(#defaults),* },
                                        stringify!(#ident)))
         }
         Fields::Unnamed(fields) => {
@@ -178,6 +435,141 @@ fn variant_combo(variant: &Variant, struct_name: &Ident) -> TokenStream {
     }
 }
 
+/// `self` matches this variant's discriminant, ignoring field values (used as the
+/// combo's "is this the selected variant" check when no `variant_eq` is supplied).
+fn variant_discriminant_check(variant: &Variant, struct_name: &Ident) -> TokenStream {
+    let ident = &variant.ident;
+    match &variant.fields {
+        Fields::Named(_) => quote!(matches!(self, #struct_name::#ident { .. })),
+        Fields::Unnamed(_) => quote!(matches!(self, #struct_name::#ident(..))),
+        Fields::Unit => quote!(matches!(self, #struct_name::#ident)),
+    }
+}
+
+/// Whether `self` counts as this variant being selected in the combo: the
+/// `#[inspect(variant_eq)]` override when given, otherwise the discriminant check.
+fn variant_is_selected(
+    variant: &Variant,
+    struct_name: &Ident,
+    attrs: &VariantAttributeArgs,
+) -> TokenStream {
+    match &attrs.variant_eq {
+        Some(variant_eq) => {
+            let variant_eq_fn = syn::Path::from_string(variant_eq)
+                .expect(format!("Could not find function: {}", variant_eq).as_str());
+            quote!(#variant_eq_fn(self))
+        }
+        None => variant_discriminant_check(variant, struct_name),
+    }
+}
+
+/// `#[inspect(carry_fields)]` combo option: builds `variant` from whatever `self`
+/// currently is, carrying over fields whose type matches rather than always
+/// falling back to `Default::default()`.
+fn variant_combo_carry_fields(
+    variant: &Variant,
+    struct_name: &Ident,
+    all_variants: &[&Variant],
+    attrs: &VariantAttributeArgs,
+) -> TokenStream {
+    let ident = &variant.ident;
+    let is_selected = variant_is_selected(variant, struct_name, attrs);
+    let arms = all_variants
+        .iter()
+        .map(|source| carry_fields_arm(variant, source, struct_name));
+    quote!(
+        if ui.selectable_label(#is_selected, stringify!(#ident)).clicked() {
+            match self {
+                #(#arms),*
+            }
+        }
+    )
+}
+
+/// Match arm that rebuilds `target` from the fields bound out of `source`, carrying
+/// over any field whose type matches (first unused match wins, deterministically in
+/// `target`'s field order) and defaulting the rest.
+fn carry_fields_arm(target: &Variant, source: &Variant, struct_name: &Ident) -> TokenStream {
+    let source_ident = &source.ident;
+    let target_ident = &target.ident;
+
+    let source_bindings: Vec<(Ident, Type)> = match &source.fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|f| (f.ident.clone().unwrap(), f.ty.clone()))
+            .collect(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, f)| (format_ident!("__src{}", i), f.ty.clone()))
+            .collect(),
+        Fields::Unit => Vec::new(),
+    };
+
+    let mut used = vec![false; source_bindings.len()];
+    let construct = match &target.fields {
+        Fields::Named(fields) => {
+            let assigns = fields.named.iter().map(|f| {
+                let field_ident = f.ident.clone().unwrap();
+                let value = find_carry_value(&f.ty, &source_bindings, &mut used);
+                quote!(#field_ident: #value)
+            });
+            quote!(#struct_name::#target_ident { #(#assigns),* })
+        }
+        Fields::Unnamed(fields) => {
+            let assigns = fields
+                .unnamed
+                .iter()
+                .map(|f| find_carry_value(&f.ty, &source_bindings, &mut used));
+            quote!(#struct_name::#target_ident ( #(#assigns),* ))
+        }
+        Fields::Unit => quote!(#struct_name::#target_ident),
+    };
+
+    // Bind unconsumed named fields as `_field` so carry_fields doesn't warn about
+    // (or, under `-D warnings`, fail to build on) the common case of switching to a
+    // variant that doesn't use every field of the one we're switching away from.
+    let pattern = match &source.fields {
+        Fields::Named(fields) => {
+            let idents = fields.named.iter().enumerate().map(|(i, f)| {
+                let field_ident = f.ident.clone().unwrap();
+                if used[i] {
+                    quote!(#field_ident)
+                } else {
+                    let binding = format_ident!("_{}", field_ident);
+                    quote!(#field_ident: #binding)
+                }
+            });
+            quote!(#struct_name::#source_ident { #(#idents),* })
+        }
+        Fields::Unnamed(fields) => {
+            let idents = (0..fields.unnamed.len()).map(|i| format_ident!("__src{}", i));
+            quote!(#struct_name::#source_ident ( #(#idents),* ))
+        }
+        Fields::Unit => quote!(#struct_name::#source_ident),
+    };
+
+    quote!(#pattern => { *self = #construct; })
+}
+
+/// First not-yet-used source binding whose type matches `target_ty`, cloned; falls
+/// back to `Default::default()` when nothing matches.
+fn find_carry_value(
+    target_ty: &Type,
+    source_bindings: &[(Ident, Type)],
+    used: &mut [bool],
+) -> TokenStream {
+    for (i, (binding, ty)) in source_bindings.iter().enumerate() {
+        if !used[i] && utils::types_equal(ty, target_ty) {
+            used[i] = true;
+            return quote!(#binding.clone());
+        }
+    }
+    quote!(Default::default())
+}
+
 fn variant_inspect_arm(variant: &Variant, struct_name: &Ident, mutable: bool) -> TokenStream {
     let ident = &variant.ident;
     let inspect_fields = inspect_fields(&variant.fields, false, mutable);
@@ -1,8 +1,10 @@
+use std::collections::HashSet;
+
 use proc_macro2::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
 use syn::Type::{Path, Reference};
-use syn::{Field, Ident, Type};
+use syn::{Field, GenericArgument, Ident, PathArguments, ReturnType, Type};
 
 use crate::AttributeArgs;
 
@@ -20,6 +22,59 @@ pub fn get_path_str(type_path: &Type) -> Option<String> {
     }
 }
 
+/// Returns whether `ty` mentions any of the given in-scope generic type parameters,
+/// looking through references, tuples, arrays/slices and path segment arguments
+/// (e.g. the `T` in `Vec<T>`, `Option<&T>` or `<T as SomeTrait>::Assoc`).
+pub(crate) fn type_contains_generic(ty: &Type, generic_idents: &HashSet<Ident>) -> bool {
+    match ty {
+        Type::Path(type_path) => {
+            if let Some(qself) = &type_path.qself {
+                if type_contains_generic(&qself.ty, generic_idents) {
+                    return true;
+                }
+            }
+            type_path.path.segments.iter().any(|segment| {
+                if generic_idents.contains(&segment.ident) {
+                    return true;
+                }
+                match &segment.arguments {
+                    PathArguments::AngleBracketed(args) => args.args.iter().any(|arg| match arg {
+                        GenericArgument::Type(ty) => type_contains_generic(ty, generic_idents),
+                        _ => false,
+                    }),
+                    PathArguments::Parenthesized(args) => {
+                        args.inputs
+                            .iter()
+                            .any(|ty| type_contains_generic(ty, generic_idents))
+                            || matches!(
+                                &args.output,
+                                ReturnType::Type(_, ty) if type_contains_generic(ty, generic_idents)
+                            )
+                    }
+                    PathArguments::None => false,
+                }
+            })
+        }
+        Type::Reference(type_ref) => type_contains_generic(&type_ref.elem, generic_idents),
+        Type::Tuple(type_tuple) => type_tuple
+            .elems
+            .iter()
+            .any(|elem| type_contains_generic(elem, generic_idents)),
+        Type::Slice(type_slice) => type_contains_generic(&type_slice.elem, generic_idents),
+        Type::Array(type_array) => type_contains_generic(&type_array.elem, generic_idents),
+        Type::Paren(type_paren) => type_contains_generic(&type_paren.elem, generic_idents),
+        Type::Group(type_group) => type_contains_generic(&type_group.elem, generic_idents),
+        Type::Ptr(type_ptr) => type_contains_generic(&type_ptr.elem, generic_idents),
+        _ => false,
+    }
+}
+
+/// Structural equality for `syn::Type`, used to decide whether two fields "unify"
+/// (e.g. when looking for a value to carry over between enum variants).
+pub(crate) fn types_equal(a: &Type, b: &Type) -> bool {
+    use quote::ToTokens;
+    a.to_token_stream().to_string() == b.to_token_stream().to_string()
+}
 pub(crate) fn get_default_function_call(
     name: &str,
     field: &TokenStream,